@@ -0,0 +1,60 @@
+//! Command-line interface: argument parsing for watch mode, GPU filtering,
+//! output format, and unit selection.
+
+use clap::{Parser, ValueEnum};
+
+use crate::usage::TempUnit;
+
+/// Reports AMD GPU sensors, power, clocks, VRAM, and per-process usage.
+#[derive(Parser, Debug)]
+#[command(name = "amdgpu-info", about, version)]
+pub struct Cli {
+    /// Re-sample on an interval instead of sampling once. JSON and text output
+    /// print one line per card per sample.
+    #[arg(long, value_name = "SECONDS")]
+    pub watch: Option<u64>,
+
+    /// Restrict output to a single card, e.g. `--card 0` for `card0`.
+    #[arg(long, value_name = "N")]
+    pub card: Option<u32>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// Unit to report temperatures in under `--format text`. JSON and
+    /// Prometheus output always report Celsius.
+    #[arg(long = "temp-unit", value_enum, default_value_t = CliTempUnit::C)]
+    pub temp_unit: CliTempUnit,
+
+    /// Drop VRAM-total and clock fields for a condensed single-line summary.
+    /// Has no effect under `--format prometheus`.
+    #[arg(long)]
+    pub basic: bool,
+}
+
+/// Selects how a sample is rendered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Text,
+    Prometheus,
+}
+
+/// Temperature unit as spelled on the command line.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum CliTempUnit {
+    C,
+    F,
+    K,
+}
+
+impl From<CliTempUnit> for TempUnit {
+    fn from(unit: CliTempUnit) -> Self {
+        match unit {
+            CliTempUnit::C => TempUnit::Celsius,
+            CliTempUnit::F => TempUnit::Fahrenheit,
+            CliTempUnit::K => TempUnit::Kelvin,
+        }
+    }
+}
@@ -1,37 +1,133 @@
 use std::error::Error;
-use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::time::Duration;
 use std::{fs, thread};
 
+use clap::Parser;
+
+use cli::{Cli, OutputFormat};
 use errors::GpuInfoError;
-use serialize::Json;
-use usage::{Units, format_units};
+use processes::{ProcessUsage, diff_usage, pci_address, sample_processes};
+use sensors::{Sensor, discover_sensors};
+use serialize::{JsonValue, Value};
+use usage::{TempUnit, Units, format_units};
 
+mod cli;
 mod errors;
+mod processes;
+mod prometheus;
+mod sensors;
 mod serialize;
 mod usage;
 
+/// Interval between the two `fdinfo` samples used to compute per-process
+/// engine-utilization percentages.
+const PROCESS_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Struct to hold GPU data.
 struct GpuData {
-    temperature: String,
-    core_clock: String,
-    power_usage: String,
-    gpu_load: String,
-    vram_used: String,
-    vram_total: String,
+    temperature_c: f64,
+    core_clock_hz: f64,
+    power_w: f64,
+    gpu_load_ratio: f64,
+    vram_used_bytes: f64,
+    vram_total_bytes: f64,
+    sensors: Vec<Sensor>,
 }
 
 // Generate JSON serialization and Display via macro
 impl_json!(GpuData {
-    temperature: "GPU Temperature",
-    gpu_load: "GPU Load",
-    core_clock: "GPU Core Clock",
-    power_usage: "GPU Power Usage",
-    vram_used: "GPU VRAM Usage",
-    vram_total: "GPU VRAM Total",
+    temperature_c: "temperature_celsius",
+    gpu_load_ratio: "gpu_load_ratio",
+    core_clock_hz: "core_clock_hz",
+    power_w: "power_watts",
+    vram_used_bytes: "vram_used_bytes",
+    vram_total_bytes: "vram_total_bytes",
+    sensors: "sensors",
 });
 
+/// Renders one card's sample as a single self-contained JSON line: its
+/// `card`/`pci` identifier, its metrics (dropping VRAM-total and clock when
+/// `basic`), and its process list, so `--watch` emits true NDJSON — one
+/// complete object per sample, not a metrics line plus a separate
+/// processes line.
+fn sample_to_json(
+    card: &str,
+    pci: &str,
+    data: &GpuData,
+    processes: &[ProcessUsage],
+    basic: bool,
+) -> String {
+    let mut fields = vec![
+        ("card".to_string(), Value::String(card.to_string())),
+        ("pci".to_string(), Value::String(pci.to_string())),
+    ];
+
+    let Value::Object(mut gpu_fields) = data.to_value() else {
+        unreachable!("GpuData::to_value always returns an Object")
+    };
+    if basic {
+        gpu_fields.retain(|(key, _)| key != "core_clock_hz" && key != "vram_total_bytes");
+    }
+    fields.append(&mut gpu_fields);
+
+    fields.push(("processes".to_string(), processes.to_value()));
+
+    Value::Object(fields).to_json()
+}
+
+/// Renders a sample as a compact human-readable line, dropping VRAM-total
+/// and clock when `basic`.
+fn render_text(card: &str, data: &GpuData, temp_unit: TempUnit, basic: bool) -> String {
+    let temp = format_units(Units::Temperature(temp_unit), data.temperature_c);
+    let load = format_units(Units::Gpu, data.gpu_load_ratio);
+    let power = format_units(Units::Power, data.power_w);
+    let vram_used = format_units(Units::Memory, data.vram_used_bytes);
+
+    if basic {
+        format!("{card}: {temp}  {load}  {power}  {vram_used}")
+    } else {
+        let clock = format_units(Units::Frequency, data.core_clock_hz);
+        let vram_total = format_units(Units::Memory, data.vram_total_bytes);
+        format!("{card}: {temp}  {load}  {clock}  {power}  {vram_used}/{vram_total}")
+    }
+}
+
+/// The `cardN` name a GPU's sysfs path ends in.
+fn card_name(gpu_path: &Path) -> String {
+    gpu_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("card?")
+        .to_string()
+}
+
+/// Restricts `gpus` to the one named `card<n>`, if `card` is set.
+fn filter_by_card(gpus: Vec<PathBuf>, card: Option<u32>) -> Vec<PathBuf> {
+    let Some(n) = card else {
+        return gpus;
+    };
+    let wanted = format!("card{n}");
+    gpus.into_iter()
+        .filter(|p| p.file_name().and_then(|n| n.to_str()) == Some(wanted.as_str()))
+        .collect()
+}
+
+/// Samples per-process GPU usage twice, `PROCESS_SAMPLE_INTERVAL` apart, and
+/// diffs the cumulative engine counters to get a percent busy per process.
+fn sample_gpu_processes(gpu_path: &Path) -> Vec<ProcessUsage> {
+    let Some(address) = pci_address(gpu_path) else {
+        return Vec::new();
+    };
+
+    let before = sample_processes(&address);
+    thread::sleep(PROCESS_SAMPLE_INTERVAL);
+    let after = sample_processes(&address);
+
+    diff_usage(&before, &after, PROCESS_SAMPLE_INTERVAL)
+}
+
 /// Reads, parses & formats one metric from sysfs.
 fn read_metric<T, F>(
     base: &Path,
@@ -55,32 +151,24 @@ fn read_gpu_data(
     gpu_path: &Path,
     hwmon_dir: &Path,
 ) -> Result<GpuData, Box<dyn Error + Send + Sync>> {
-    let temp_val = read_metric::<i32, _>(hwmon_dir, "temp1_input", |m| m as f64 / 1000.0)?;
-    let temperature = format_units(Units::Temperature, temp_val);
-
-    let freq_val = read_metric::<f64, _>(hwmon_dir, "freq1_input", |hz| hz)?;
-    let core_clock = format_units(Units::Frequency, freq_val);
-
-    let power_val = read_metric::<f64, _>(hwmon_dir, "power1_average", |u| u / 1_000_000.0)?;
-    let power_usage = format_units(Units::Power, power_val);
-
-    let load_val =
+    let temperature_c = read_metric::<i32, _>(hwmon_dir, "temp1_input", |m| m as f64 / 1000.0)?;
+    let core_clock_hz = read_metric::<f64, _>(hwmon_dir, "freq1_input", |hz| hz)?;
+    let power_w = read_metric::<f64, _>(hwmon_dir, "power1_average", |u| u / 1_000_000.0)?;
+    let gpu_load_ratio =
         read_metric::<f32, _>(gpu_path, "device/gpu_busy_percent", |p| p as f64 / 100.0)?;
-    let gpu_load = format_units(Units::Gpu, load_val);
-
-    let used_val = read_metric::<f64, _>(gpu_path, "device/mem_info_vram_used", |b| b)?;
-    let vram_used = format_units(Units::Memory, used_val);
+    let vram_used_bytes = read_metric::<f64, _>(gpu_path, "device/mem_info_vram_used", |b| b)?;
+    let vram_total_bytes = read_metric::<f64, _>(gpu_path, "device/mem_info_vram_total", |b| b)?;
 
-    let total_val = read_metric::<f64, _>(gpu_path, "device/mem_info_vram_total", |b| b)?;
-    let vram_total = format_units(Units::Memory, total_val);
+    let sensors = discover_sensors(hwmon_dir);
 
     Ok(GpuData {
-        temperature,
-        core_clock,
-        power_usage,
-        gpu_load,
-        vram_used,
-        vram_total,
+        temperature_c,
+        core_clock_hz,
+        power_w,
+        gpu_load_ratio,
+        vram_used_bytes,
+        vram_total_bytes,
+        sensors,
     })
 }
 
@@ -117,32 +205,72 @@ fn find_hwmon_dir(gpu_path: &Path) -> Result<PathBuf, Box<dyn Error + Send + Syn
     Err(Box::new(GpuInfoError("No hwmon directory found".into())))
 }
 
-/// Main logic: detect GPUs, read stats concurrently, print JSON.
+/// Main logic: detect GPUs, read stats concurrently, print one sample (or,
+/// under `--watch`, one sample per interval).
 fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
-    let gpus = detect_amd_gpus()?;
-    if gpus.is_empty() {
-        println!("No AMD GPUs detected.");
-        return Ok(());
-    }
+    let cli = Cli::parse();
+    let temp_unit: TempUnit = cli.temp_unit.into();
 
-    let handles = gpus
-        .into_iter()
-        .map(|gpu| {
+    loop {
+        let gpus = filter_by_card(detect_amd_gpus()?, cli.card);
+        if gpus.is_empty() {
+            println!("No AMD GPUs detected.");
+            return Ok(());
+        }
+
+        let want_processes = cli.format == OutputFormat::Json;
+
+        let handles = gpus.into_iter().map(|gpu| {
             thread::spawn(move || {
                 let hwmon = find_hwmon_dir(&gpu)?;
-                read_gpu_data(&gpu, &hwmon)
+                let data = read_gpu_data(&gpu, &hwmon)?;
+                let processes = if want_processes {
+                    sample_gpu_processes(&gpu)
+                } else {
+                    Vec::new()
+                };
+                let pci = pci_address(&gpu).unwrap_or_else(|| "unknown".to_string());
+                Ok::<_, Box<dyn Error + Send + Sync>>((card_name(&gpu), pci, data, processes))
             })
         });
 
-    for h in handles {
-        match h
-            .join()
-            .unwrap()
-        {
-            Ok(data) => println!("{}", data.to_json()),
-            Err(e) => eprintln!("Error reading GPU data: {}", e),
+        let mut samples = Vec::new();
+        for h in handles {
+            match h
+                .join()
+                .unwrap()
+            {
+                Ok(sample) => samples.push(sample),
+                Err(e) => eprintln!("Error reading GPU data: {}", e),
+            }
+        }
+
+        match cli.format {
+            OutputFormat::Json => {
+                for (card, pci, data, processes) in &samples {
+                    println!("{}", sample_to_json(card, pci, data, processes, cli.basic));
+                }
+            }
+            OutputFormat::Text => {
+                for (card, _, data, _) in &samples {
+                    println!("{}", render_text(card, data, temp_unit, cli.basic));
+                }
+            }
+            OutputFormat::Prometheus => {
+                let cards: Vec<(&str, &str, &GpuData)> = samples
+                    .iter()
+                    .map(|(card, pci, data, _)| (card.as_str(), pci.as_str(), data))
+                    .collect();
+                print!("{}", prometheus::render_all(&cards));
+            }
+        }
+
+        match cli.watch {
+            Some(interval) => thread::sleep(Duration::from_secs(interval)),
+            None => break,
         }
     }
+
     Ok(())
 }
 
@@ -0,0 +1,262 @@
+//! Per-process GPU usage accounting via the DRM `fdinfo` interface: every
+//! open DRM file descriptor under `/proc/<pid>/fdinfo/<fd>` exposes
+//! `drm-driver`, `drm-pdev`, `drm-memory-vram`, and `drm-engine-*` lines,
+//! letting us attribute VRAM and engine busy time to the process holding it.
+
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+use crate::serialize::{JsonValue, Value};
+
+/// Cumulative counters for one process, summed across all its DRM fds.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSample {
+    pub pid: u32,
+    pub name: String,
+    pub vram_bytes: u64,
+    pub gfx_ns: u64,
+    pub compute_ns: u64,
+}
+
+/// A process's GPU usage over the interval between two samples.
+#[derive(Debug, Clone)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    pub vram_bytes: u64,
+    pub gfx_percent: f64,
+    pub compute_percent: f64,
+}
+
+impl JsonValue for ProcessUsage {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("pid".to_string(), Value::Number(self.pid as f64)),
+            ("name".to_string(), Value::String(self.name.clone())),
+            ("vram_bytes".to_string(), Value::Number(self.vram_bytes as f64)),
+            ("gfx_percent".to_string(), Value::Number(self.gfx_percent)),
+            (
+                "compute_percent".to_string(),
+                Value::Number(self.compute_percent),
+            ),
+        ])
+    }
+}
+
+/// Reads the PCI address (e.g. `0000:03:00.0`) of the GPU at `gpu_path` by
+/// resolving its `device` symlink under `/sys/class/drm`.
+pub fn pci_address(gpu_path: &Path) -> Option<String> {
+    fs::read_link(gpu_path.join("device"))
+        .ok()?
+        .file_name()?
+        .to_str()
+        .map(str::to_string)
+}
+
+/// Scans `/proc/*/fdinfo/*` for DRM fds backed by `pci_address`, aggregating
+/// VRAM and engine busy-time counters per PID.
+pub fn sample_processes(pci_address: &str) -> HashMap<u32, ProcessSample> {
+    let mut samples: HashMap<u32, ProcessSample> = HashMap::new();
+
+    let Ok(proc_entries) = fs::read_dir("/proc") else {
+        return samples;
+    };
+
+    for proc_entry in proc_entries.filter_map(Result::ok) {
+        let Some(pid) = proc_entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let Ok(fd_entries) = fs::read_dir(proc_entry.path().join("fdinfo")) else {
+            continue;
+        };
+
+        for fd_entry in fd_entries.filter_map(Result::ok) {
+            let Ok(contents) = fs::read_to_string(fd_entry.path()) else {
+                continue;
+            };
+            let Some(fields) = parse_fdinfo(&contents) else {
+                continue;
+            };
+            if fields.driver != "amdgpu" || fields.pdev != pci_address {
+                continue;
+            }
+
+            let sample = samples.entry(pid).or_insert_with(|| ProcessSample {
+                pid,
+                name: read_comm(pid),
+                ..Default::default()
+            });
+            sample.vram_bytes += fields.vram_bytes;
+            sample.gfx_ns += fields.gfx_ns;
+            sample.compute_ns += fields.compute_ns;
+        }
+    }
+
+    samples
+}
+
+/// Computes each process's engine-utilization percent by diffing cumulative
+/// busy time between two samples spaced `elapsed` apart.
+pub fn diff_usage(
+    before: &HashMap<u32, ProcessSample>,
+    after: &HashMap<u32, ProcessSample>,
+    elapsed: Duration,
+) -> Vec<ProcessUsage> {
+    let elapsed_ns = elapsed.as_nanos().max(1) as f64;
+
+    after
+        .values()
+        .map(|sample| {
+            let prev = before.get(&sample.pid);
+            let gfx_delta = sample
+                .gfx_ns
+                .saturating_sub(prev.map_or(0, |p| p.gfx_ns));
+            let compute_delta = sample
+                .compute_ns
+                .saturating_sub(prev.map_or(0, |p| p.compute_ns));
+
+            ProcessUsage {
+                pid: sample.pid,
+                name: sample.name.clone(),
+                vram_bytes: sample.vram_bytes,
+                gfx_percent: gfx_delta as f64 / elapsed_ns * 100.0,
+                compute_percent: compute_delta as f64 / elapsed_ns * 100.0,
+            }
+        })
+        .collect()
+}
+
+/// Fields parsed out of a single `fdinfo` file.
+struct FdinfoFields {
+    driver: String,
+    pdev: String,
+    vram_bytes: u64,
+    gfx_ns: u64,
+    compute_ns: u64,
+}
+
+/// Parses the `drm-*` lines out of one `/proc/<pid>/fdinfo/<fd>` file.
+fn parse_fdinfo(contents: &str) -> Option<FdinfoFields> {
+    let mut driver = String::new();
+    let mut pdev = String::new();
+    let mut vram_bytes = 0u64;
+    let mut gfx_ns = 0u64;
+    let mut compute_ns = 0u64;
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "drm-driver" => driver = value.to_string(),
+            "drm-pdev" => pdev = value.to_string(),
+            // Older amdgpu kernels report `drm-memory-vram`; newer ones use
+            // the generic DRM client-stats key `drm-total-vram` instead.
+            "drm-memory-vram" | "drm-total-vram" => vram_bytes = parse_kib(value),
+            "drm-engine-gfx" => gfx_ns = parse_ns(value),
+            "drm-engine-compute" => compute_ns = parse_ns(value),
+            _ => {}
+        }
+    }
+
+    if driver.is_empty() {
+        return None;
+    }
+
+    Some(FdinfoFields {
+        driver,
+        pdev,
+        vram_bytes,
+        gfx_ns,
+        compute_ns,
+    })
+}
+
+/// Parses a `"123 KiB"` value into bytes.
+fn parse_kib(value: &str) -> u64 {
+    value
+        .split_whitespace()
+        .next()
+        .and_then(|n| n.parse::<u64>().ok())
+        .map(|kib| kib * 1024)
+        .unwrap_or(0)
+}
+
+/// Parses a `"123456789 ns"` value into nanoseconds.
+fn parse_ns(value: &str) -> u64 {
+    value
+        .split_whitespace()
+        .next()
+        .and_then(|n| n.parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+/// Reads `/proc/<pid>/comm`, falling back to a placeholder if the process
+/// has already exited.
+fn read_comm(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{pid}/comm"))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| format!("pid-{pid}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_kib_converts_kibibytes_to_bytes() {
+        assert_eq!(parse_kib("1024 KiB"), 1024 * 1024);
+        assert_eq!(parse_kib("0 KiB"), 0);
+    }
+
+    #[test]
+    fn parse_kib_defaults_to_zero_on_garbage() {
+        assert_eq!(parse_kib("not a number"), 0);
+        assert_eq!(parse_kib(""), 0);
+    }
+
+    #[test]
+    fn parse_ns_reads_the_leading_integer() {
+        assert_eq!(parse_ns("123456789 ns"), 123456789);
+        assert_eq!(parse_ns("garbage"), 0);
+    }
+
+    #[test]
+    fn parse_fdinfo_reads_memory_vram_key() {
+        let contents = "\
+drm-driver: amdgpu
+drm-pdev: 0000:03:00.0
+drm-memory-vram: 512 KiB
+drm-engine-gfx: 1000000 ns
+drm-engine-compute: 2000000 ns
+";
+        let fields = parse_fdinfo(contents).expect("valid fdinfo");
+        assert_eq!(fields.driver, "amdgpu");
+        assert_eq!(fields.pdev, "0000:03:00.0");
+        assert_eq!(fields.vram_bytes, 512 * 1024);
+        assert_eq!(fields.gfx_ns, 1_000_000);
+        assert_eq!(fields.compute_ns, 2_000_000);
+    }
+
+    #[test]
+    fn parse_fdinfo_reads_total_vram_key() {
+        let contents = "\
+drm-driver: amdgpu
+drm-pdev: 0000:03:00.0
+drm-total-vram: 256 KiB
+";
+        let fields = parse_fdinfo(contents).expect("valid fdinfo");
+        assert_eq!(fields.vram_bytes, 256 * 1024);
+    }
+
+    #[test]
+    fn parse_fdinfo_returns_none_without_a_driver() {
+        let contents = "drm-pdev: 0000:03:00.0\n";
+        assert!(parse_fdinfo(contents).is_none());
+    }
+}
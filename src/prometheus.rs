@@ -0,0 +1,125 @@
+//! Renders collected metrics in the Prometheus text exposition format
+//! (<https://prometheus.io/docs/instrumenting/exposition_formats/>), so the
+//! tool can back a node-exporter-style endpoint or textfile collector.
+
+use std::fmt::Write as _;
+
+use crate::GpuData;
+use crate::sensors::SensorKind;
+
+/// A metric family's name, help text, and Prometheus type.
+struct Metric {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+}
+
+const TEMPERATURE: Metric = Metric {
+    name: "amdgpu_temperature_celsius",
+    help: "GPU sensor temperature in degrees Celsius.",
+    kind: "gauge",
+};
+const POWER: Metric = Metric {
+    name: "amdgpu_power_watts",
+    help: "GPU power draw in watts.",
+    kind: "gauge",
+};
+const CORE_CLOCK: Metric = Metric {
+    name: "amdgpu_core_clock_hertz",
+    help: "GPU core clock frequency in hertz.",
+    kind: "gauge",
+};
+const BUSY_RATIO: Metric = Metric {
+    name: "amdgpu_busy_ratio",
+    help: "GPU busy time as a ratio from 0.0 to 1.0.",
+    kind: "gauge",
+};
+const VRAM_USED: Metric = Metric {
+    name: "amdgpu_vram_used_bytes",
+    help: "VRAM currently in use, in bytes.",
+    kind: "gauge",
+};
+const VRAM_TOTAL: Metric = Metric {
+    name: "amdgpu_vram_total_bytes",
+    help: "Total VRAM available, in bytes.",
+    kind: "gauge",
+};
+
+fn write_header(out: &mut String, metric: &Metric) {
+    let _ = writeln!(out, "# HELP {} {}", metric.name, metric.help);
+    let _ = writeln!(out, "# TYPE {} {}", metric.name, metric.kind);
+}
+
+/// Escapes `"` and `\` in a label value per the Prometheus text format.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Renders every card's metrics as Prometheus text-exposition samples,
+/// labeled with each card's `cardN` name and PCI address. Each metric
+/// family's `# HELP`/`# TYPE` header is written once, followed by that
+/// family's sample for every card, as the exposition format requires.
+pub fn render_all(cards: &[(&str, &str, &GpuData)]) -> String {
+    let rows: Vec<(String, &GpuData)> = cards
+        .iter()
+        .map(|(card, pci, data)| {
+            let labels = format!(
+                "card=\"{}\", pci=\"{}\"",
+                escape_label(card),
+                escape_label(pci)
+            );
+            (labels, *data)
+        })
+        .collect();
+
+    let mut out = String::new();
+
+    write_header(&mut out, &TEMPERATURE);
+    for (labels, data) in &rows {
+        for sensor in data
+            .sensors
+            .iter()
+            .filter(|s| s.kind == SensorKind::Temperature)
+        {
+            let _ = writeln!(
+                out,
+                "{}{{{}, sensor=\"{}\"}} {}",
+                TEMPERATURE.name,
+                labels,
+                escape_label(&sensor.name),
+                sensor.value
+            );
+        }
+    }
+
+    write_header(&mut out, &POWER);
+    for (labels, data) in &rows {
+        let _ = writeln!(out, "{}{{{}}} {}", POWER.name, labels, data.power_w);
+    }
+
+    write_header(&mut out, &CORE_CLOCK);
+    for (labels, data) in &rows {
+        let _ = writeln!(out, "{}{{{}}} {}", CORE_CLOCK.name, labels, data.core_clock_hz);
+    }
+
+    write_header(&mut out, &BUSY_RATIO);
+    for (labels, data) in &rows {
+        let _ = writeln!(out, "{}{{{}}} {}", BUSY_RATIO.name, labels, data.gpu_load_ratio);
+    }
+
+    write_header(&mut out, &VRAM_USED);
+    for (labels, data) in &rows {
+        let _ = writeln!(out, "{}{{{}}} {}", VRAM_USED.name, labels, data.vram_used_bytes);
+    }
+
+    write_header(&mut out, &VRAM_TOTAL);
+    for (labels, data) in &rows {
+        let _ = writeln!(
+            out,
+            "{}{{{}}} {}",
+            VRAM_TOTAL.name, labels, data.vram_total_bytes
+        );
+    }
+
+    out
+}
@@ -0,0 +1,136 @@
+//! Sensor discovery modeled on how Linux `hwmon` exposes data: a hwmon
+//! directory holds a flat list of `<type><index>_<field>` files, where the
+//! companion `_label` file (when present) gives the kernel's human name for
+//! that channel and `_max`/`_crit`/`_min` give its alerting thresholds.
+
+use std::{fs, path::Path};
+
+use crate::serialize::{JsonValue, Value};
+
+/// The physical quantity a [`Sensor`] measures.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SensorKind {
+    Temperature, // °C
+    Fan,         // RPM
+    Voltage,     // V
+    Power,       // W
+}
+
+impl SensorKind {
+    /// The lowercase name used in serialized output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "temperature",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "voltage",
+            SensorKind::Power => "power",
+        }
+    }
+}
+
+/// A single hwmon channel, e.g. the "junction" temperature or "fan1" speed.
+#[derive(Debug, Clone)]
+pub struct Sensor {
+    pub name: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+    pub min: Option<f64>,
+}
+
+impl JsonValue for Sensor {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("name".to_string(), Value::String(self.name.clone())),
+            ("kind".to_string(), Value::String(self.kind.as_str().to_string())),
+            ("value".to_string(), Value::Number(self.value)),
+            ("max".to_string(), self.max.to_value()),
+            ("crit".to_string(), self.crit.to_value()),
+            ("min".to_string(), self.min.to_value()),
+        ])
+    }
+}
+
+/// Scans `hwmon_dir` for every `temp*_input`, `fan*_input`, `in*_input`, and
+/// `power*_average`/`power*_input` channel, reading each one's label and
+/// thresholds when the kernel exposes them.
+pub fn discover_sensors(hwmon_dir: &Path) -> Vec<Sensor> {
+    let mut sensors = Vec::new();
+    sensors.extend(scan_channels(hwmon_dir, "temp", SensorKind::Temperature, 1000.0));
+    sensors.extend(scan_channels(hwmon_dir, "fan", SensorKind::Fan, 1.0));
+    sensors.extend(scan_channels(hwmon_dir, "in", SensorKind::Voltage, 1000.0));
+    sensors.extend(scan_channels(hwmon_dir, "power", SensorKind::Power, 1_000_000.0));
+    sensors
+}
+
+/// Finds every distinct channel index for `prefix` under `dir` and reads each one.
+fn scan_channels(dir: &Path, prefix: &str, kind: SensorKind, divisor: f64) -> Vec<Sensor> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut indices: Vec<u32> = entries
+        .filter_map(Result::ok)
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter_map(|name| parse_index(&name, prefix))
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+
+    indices
+        .into_iter()
+        .filter_map(|idx| read_channel(dir, prefix, idx, kind, divisor))
+        .collect()
+}
+
+/// Matches e.g. `temp3_input` or `power2_average`, returning the channel index.
+fn parse_index(file_name: &str, prefix: &str) -> Option<u32> {
+    let rest = file_name.strip_prefix(prefix)?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn read_channel(
+    dir: &Path,
+    prefix: &str,
+    idx: u32,
+    kind: SensorKind,
+    divisor: f64,
+) -> Option<Sensor> {
+    let input_path = [format!("{prefix}{idx}_input"), format!("{prefix}{idx}_average")]
+        .into_iter()
+        .map(|f| dir.join(f))
+        .find(|p| p.exists())?;
+
+    let raw: f64 = fs::read_to_string(&input_path).ok()?.trim().parse().ok()?;
+    let value = raw / divisor;
+
+    let name = fs::read_to_string(dir.join(format!("{prefix}{idx}_label")))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| format!("{prefix}{idx}"));
+
+    let max = read_threshold(dir, prefix, idx, "max", divisor);
+    let crit = read_threshold(dir, prefix, idx, "crit", divisor);
+    let min = read_threshold(dir, prefix, idx, "min", divisor);
+
+    Some(Sensor {
+        name,
+        kind,
+        value,
+        max,
+        crit,
+        min,
+    })
+}
+
+/// Reads `<prefix><idx>_<suffix>` (e.g. `temp1_crit`) if the kernel provides it.
+fn read_threshold(dir: &Path, prefix: &str, idx: u32, suffix: &str, divisor: f64) -> Option<f64> {
+    fs::read_to_string(dir.join(format!("{prefix}{idx}_{suffix}")))
+        .ok()
+        .and_then(|s| s.trim().parse::<f64>().ok())
+        .map(|v| v / divisor)
+}
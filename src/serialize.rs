@@ -1,39 +1,238 @@
-//! Thismodule provides functionality for serializing data structures
-//! to JSON format and implementing `Display` for them.
+//! A small, dependency-free JSON encoder. [`Value`] covers the handful of
+//! kinds this crate's metrics need (numbers, strings, bools, objects,
+//! arrays); [`JsonValue`] converts typed fields into one; `impl_json!` wires
+//! a struct's fields into an object `Value` and implements [`Json`]/
+//! `Display` from it.
+
+use std::fmt::Write as _;
+
+/// A JSON value restricted to the kinds this crate's metrics need.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Renders this value as JSON text.
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        self.write_json(&mut s);
+        s
+    }
+
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => {
+                let _ = write!(out, "{}", *n as i64);
+            }
+            Value::Number(n) => {
+                let _ = write!(out, "{}", n);
+            }
+            Value::String(s) => write_escaped_string(s, out),
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write_json(out);
+                }
+                out.push(']');
+            }
+            Value::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    write_escaped_string(key, out);
+                    out.push_str(": ");
+                    value.write_json(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Escapes `"`, `\`, and control characters per the JSON spec.
+fn write_escaped_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Converts a typed field into a JSON [`Value`].
+pub trait JsonValue {
+    fn to_value(&self) -> Value;
+}
+
+impl JsonValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl JsonValue for str {
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl JsonValue for String {
+    fn to_value(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl JsonValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+macro_rules! impl_json_value_for_number {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl JsonValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Number(*self as f64)
+                }
+            }
+        )*
+    };
+}
+impl_json_value_for_number!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T: JsonValue> JsonValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(v) => v.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: JsonValue> JsonValue for [T] {
+    fn to_value(&self) -> Value {
+        Value::Array(self.iter().map(JsonValue::to_value).collect())
+    }
+}
+
+impl<T: JsonValue> JsonValue for Vec<T> {
+    fn to_value(&self) -> Value {
+        self.as_slice().to_value()
+    }
+}
+
+impl<T: JsonValue + ?Sized> JsonValue for &T {
+    fn to_value(&self) -> Value {
+        (*self).to_value()
+    }
+}
 
 /// This trait defines a method for converting a struct to a JSON string.
 pub trait Json {
     fn to_json(&self) -> String;
 }
 
+/// Builds an object [`Value`] from a struct's fields and implements
+/// [`JsonValue`]/[`Json`]/`Display` from it. Each field's type must
+/// implement [`JsonValue`] (strings, numbers, bools, `Option`/`Vec` of
+/// those, or a nested type that implements `JsonValue` itself). Exposing
+/// the object as a [`Value`] (not just a JSON string) lets callers splice
+/// a struct's fields into a larger object instead of only printing it
+/// standalone.
 #[macro_export]
 macro_rules! impl_json {
     ($ty:ident { $($field:ident : $key:expr),* $(,)? }) => {
+        impl $crate::serialize::JsonValue for $ty {
+            fn to_value(&self) -> $crate::serialize::Value {
+                let fields: Vec<(String, $crate::serialize::Value)> = vec![
+                    $(($key.to_string(), $crate::serialize::JsonValue::to_value(&self.$field)),)*
+                ];
+                $crate::serialize::Value::Object(fields)
+            }
+        }
+
         impl $crate::serialize::Json for $ty {
-            #[allow(unused_assignments)]
             fn to_json(&self) -> String {
-                let mut s = String::new();
-                let mut first = true;
-
-                s.push('{');
-                $(
-                    if !first { s.push_str(", "); }
-                    first = false;
-                    s.push('"');
-                    s.push_str($key);
-                    s.push_str("\": \"");
-                    s.push_str(&self.$field);
-                    s.push('"');
-                )*
-                s.push('}');
-                s
+                $crate::serialize::JsonValue::to_value(self).to_json()
             }
         }
 
-        impl Display for $ty {
+        impl std::fmt::Display for $ty {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(f, "{}", self.to_json())
+                write!(f, "{}", $crate::serialize::Json::to_json(self))
             }
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_quotes_backslashes_and_control_chars() {
+        let value = Value::String("say \"hi\"\\bye\n\t\u{1}".to_string());
+        assert_eq!(value.to_json(), "\"say \\\"hi\\\"\\\\bye\\n\\t\\u0001\"");
+    }
+
+    #[test]
+    fn whole_numbers_render_without_a_decimal_point() {
+        assert_eq!(Value::Number(45.0).to_json(), "45");
+        assert_eq!(Value::Number(-3.0).to_json(), "-3");
+    }
+
+    #[test]
+    fn fractional_numbers_keep_their_decimal_point() {
+        assert_eq!(Value::Number(45.5).to_json(), "45.5");
+    }
+
+    #[test]
+    fn renders_nested_objects_and_arrays() {
+        let value = Value::Object(vec![
+            ("name".to_string(), Value::String("edge".to_string())),
+            (
+                "readings".to_string(),
+                Value::Array(vec![Value::Number(1.0), Value::Number(2.5)]),
+            ),
+            ("max".to_string(), Value::Null),
+        ]);
+        assert_eq!(
+            value.to_json(),
+            "{\"name\": \"edge\", \"readings\": [1, 2.5], \"max\": null}"
+        );
+    }
+
+    #[test]
+    fn option_and_vec_convert_through_json_value() {
+        let some: Option<f64> = Some(3.0);
+        let none: Option<f64> = None;
+        assert_eq!(some.to_value().to_json(), "3");
+        assert_eq!(none.to_value().to_json(), "null");
+
+        let values = vec![1u32, 2, 3];
+        assert_eq!(values.to_value().to_json(), "[1, 2, 3]");
+    }
+}
@@ -4,11 +4,19 @@
 /// Units of measurement for formatting.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Units {
-    Temperature, // °C
-    Memory,      // bytes → KB/MB/…
-    Gpu,         // 0.0–1.0 → %
-    Power,       // W
-    Frequency,   // Hz → kHz/MHz/…
+    Temperature(TempUnit), // °C/°F/K
+    Memory,                // bytes → KB/MB/…
+    Gpu,                   // 0.0–1.0 → %
+    Power,                 // W
+    Frequency,             // Hz → kHz/MHz/…
+}
+
+/// Unit a Celsius reading should be converted to before formatting.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TempUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
 }
 
 /// Generic “scale-and-suffix” formatter
@@ -37,9 +45,12 @@ const HERTZ_SUFFIXES: [(&str, f64); 4] = [
 ];
 
 /// Formats a value in the specified unit, returning a string with the appropriate suffix.
+/// `value` is always the base unit (Celsius for temperature), regardless of display unit.
 pub fn format_units(unit: Units, value: f64) -> String {
     match unit {
-        Units::Temperature => format!("{:.1} °C", value),
+        Units::Temperature(TempUnit::Celsius) => format!("{:.1} °C", value),
+        Units::Temperature(TempUnit::Fahrenheit) => format!("{:.1} °F", value * 9.0 / 5.0 + 32.0),
+        Units::Temperature(TempUnit::Kelvin) => format!("{:.1} K", value + 273.15),
         Units::Gpu => format!("{:.1}%", value * 100.0),
         Units::Power => format!("{:.1} W", value),
         Units::Memory => format_scaled(value, &BYTE_SUFFIXES),